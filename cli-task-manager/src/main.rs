@@ -1,17 +1,41 @@
+mod repository;
+
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
-use serde::{Deserialize, Serialize};
-use std::fs::{File, OpenOptions};
-use std::io::Read;
+use repository::{JsonRepository, Repository, SqliteRepository, Task};
 use std::process;
 
-const TASK_FILE: &str = "tasks.json";
+/// Resolves the path to a data file under the XDG data directory for this
+/// app (e.g. `~/.local/share/cli-task-manager/<file_name>`), creating parent
+/// directories as needed. `TASK_DATA_DIR` overrides the XDG directory with an
+/// arbitrary one, which keeps tests and ad-hoc runs independent of the real
+/// XDG location; `file_name` is still joined onto it, so distinct data files
+/// (e.g. the active list and the archive) never collide under one override.
+fn resolve_data_file(file_name: &str) -> String {
+    if let Ok(dir) = std::env::var("TASK_DATA_DIR") {
+        let dir = std::path::PathBuf::from(dir);
+        std::fs::create_dir_all(&dir).expect("Could not create override data directory");
+        return dir.join(file_name).to_string_lossy().into_owned();
+    }
+    let xdg_dirs = xdg::BaseDirectories::with_prefix("cli-task-manager")
+        .expect("Could not resolve XDG base directories");
+    xdg_dirs
+        .place_data_file(file_name)
+        .expect("Could not create XDG data directory")
+        .to_string_lossy()
+        .into_owned()
+}
 
 #[derive(Parser)]
 #[command(name = "CLI Task Manager")]
 #[command(about = "A simple CLI task manager", long_about = None)]
 struct Cli {
+    /// Storage backend to use: "json" (default) or "sqlite". Falls back to
+    /// the TASK_BACKEND environment variable, then "json".
+    #[arg(long, global = true)]
+    backend: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,165 +46,448 @@ enum Commands {
     Add {
         #[arg(short, long)]
         description: String,
+        /// Due date in RFC3339 format, e.g. 2026-08-01T00:00:00Z
+        #[arg(long)]
+        due: Option<String>,
+        /// Comma-separated list of tags
+        #[arg(long)]
+        tags: Option<String>,
+        /// Short unique name/slug to reference the task by instead of its ID
+        #[arg(short, long)]
+        name: Option<String>,
     },
     /// List all tasks
-    List,
-    /// Remove a task by ID
+    List {
+        /// Only show tasks whose due date has passed
+        #[arg(long)]
+        overdue: bool,
+        /// List archived (finished) tasks instead of active ones
+        #[arg(long)]
+        finished: bool,
+    },
+    /// Remove a task by ID or name
     Remove {
         #[arg(short, long)]
-        id: u32,
+        id: Option<u32>,
+        #[arg(short, long)]
+        name: Option<String>,
     },
-    /// Edit a task description by ID
+    /// Edit a task's description by ID or name
     Edit {
         #[arg(short, long)]
-        id: u32,
+        id: Option<u32>,
+        #[arg(short, long)]
+        name: Option<String>,
         #[arg(short, long)]
         description: String,
+        /// Due date in RFC3339 format, e.g. 2026-08-01T00:00:00Z
+        #[arg(long)]
+        due: Option<String>,
+        /// Comma-separated list of tags
+        #[arg(long)]
+        tags: Option<String>,
+        /// Assign (or change) the task's short unique name
+        #[arg(long)]
+        rename: Option<String>,
     },
-    /// Toggle the completed state of a task by ID
+    /// Toggle the completed state of a task by ID or name
     Toggle {
         #[arg(short, long)]
-        id: u32,
+        id: Option<u32>,
+        #[arg(short, long)]
+        name: Option<String>,
     },
     /// Fuzzy search tasks by description, ID, or status
     Search {
         #[arg(short, long)]
         query: String,
     },
+    /// Reorder a task relative to another task
+    Priority {
+        #[arg(short, long)]
+        id: u32,
+        #[command(subcommand)]
+        position: PriorityPosition,
+    },
+    /// Start tracking time on a task by ID
+    Start {
+        #[arg(short, long)]
+        id: u32,
+    },
+    /// Pause the currently active task
+    Pause,
+    /// Pause and mark the currently active task as completed
+    Finish,
+    /// Show the currently active task and its elapsed time
+    Status,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
-struct Task {
-    id: u32,
-    description: String,
-    completed: bool,
+#[derive(Subcommand)]
+enum PriorityPosition {
+    /// Move the task immediately before the referenced task
+    Before {
+        #[arg(short, long)]
+        id: u32,
+    },
+    /// Move the task immediately after the referenced task
+    After {
+        #[arg(short, long)]
+        id: u32,
+    },
 }
 
-fn load_tasks() -> Vec<Task> {
-    let file = File::open(TASK_FILE);
-    match file {
-        Ok(mut file) => {
-            let mut contents = String::new();
-            file.read_to_string(&mut contents)
-                .expect("Could not read file");
-            serde_json::from_str(&contents).unwrap_or_else(|_| Vec::new())
-        }
-        Err(_) => Vec::new(), // Return empty if file doesn't exist
+fn resolve_backend_name(backend: Option<&str>) -> String {
+    backend
+        .map(|b| b.to_string())
+        .or_else(|| std::env::var("TASK_BACKEND").ok())
+        .unwrap_or_else(|| "json".to_string())
+}
+
+/// Builds the storage backend selected by `--backend`, falling back to the
+/// `TASK_BACKEND` environment variable and then the JSON backend.
+fn build_repository(backend: Option<&str>) -> Box<dyn Repository> {
+    match resolve_backend_name(backend).as_str() {
+        "sqlite" => Box::new(SqliteRepository::new(resolve_data_file("tasks.sqlite"))),
+        _ => Box::new(JsonRepository::new(resolve_data_file("tasks.json"))),
     }
 }
 
-fn save_tasks(tasks: &Vec<Task>) {
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(TASK_FILE)
-        .expect("Could not open file");
-    serde_json::to_writer_pretty(file, tasks).expect("Could not write to file");
+/// Builds the archive backend that holds finished tasks, mirroring
+/// `build_repository` but pointed at a separate data file so active and
+/// finished tasks never live side by side.
+fn build_archive_repository(backend: Option<&str>) -> Box<dyn Repository> {
+    match resolve_backend_name(backend).as_str() {
+        "sqlite" => Box::new(SqliteRepository::new(resolve_data_file("finished_tasks.sqlite"))),
+        _ => Box::new(JsonRepository::new(resolve_data_file("finished_tasks.json"))),
+    }
+}
+
+/// Parses a comma-separated tag list, dropping empty entries.
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
 }
 
-fn add_task(description: String) {
-    let mut tasks = load_tasks();
+/// Validates that `due` parses as RFC3339, erroring with a user-facing
+/// message if not.
+fn parse_due(due: &str) -> Result<String, String> {
+    if DateTime::parse_from_rfc3339(due).is_err() {
+        return Err(format!("Invalid due date '{}': expected RFC3339 format.", due));
+    }
+    Ok(due.to_string())
+}
+
+/// Validates that `name` is non-empty, whitespace-free (so it stays a safe
+/// shell identifier), and not already used by another task.
+fn validate_name(tasks: &[Task], name: &str, excluding_id: Option<u32>) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Task name must not be empty.".to_string());
+    }
+    if name.chars().any(|c| c.is_whitespace()) {
+        return Err(format!("Task name '{}' must not contain whitespace.", name));
+    }
+    let collision = tasks
+        .iter()
+        .any(|t| t.name.as_deref() == Some(name) && Some(t.id) != excluding_id);
+    if collision {
+        return Err(format!("Task name '{}' is already in use.", name));
+    }
+    Ok(())
+}
+
+/// Resolves a `--id`/`--name` pair to a concrete task ID, erroring if neither
+/// was given, the name doesn't match any task, or it matches more than one.
+fn resolve_task_id(repo: &dyn Repository, id: Option<u32>, name: Option<&str>) -> Result<u32, String> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    let name = match name {
+        Some(name) => name,
+        None => return Err("Either --id or --name must be provided.".to_string()),
+    };
+    let matches: Vec<u32> = repo
+        .all_tasks()
+        .iter()
+        .filter(|t| t.name.as_deref() == Some(name))
+        .map(|t| t.id)
+        .collect();
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(format!("No task named '{}'.", name)),
+        _ => Err(format!("Task name '{}' is ambiguous.", name)),
+    }
+}
+
+fn add_task(
+    repo: &mut dyn Repository,
+    description: String,
+    due: Option<String>,
+    tags: Option<String>,
+    name: Option<String>,
+) -> Result<(), String> {
+    let tasks = repo.all_tasks();
+    if let Some(name) = &name {
+        validate_name(&tasks, name, None)?;
+    }
+    let due = due.map(|d| parse_due(&d)).transpose()?;
     let next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
-    tasks.push(Task {
+    let next_priority = tasks.iter().map(|t| t.priority).max().unwrap_or(0) + 1;
+    repo.insert_task(Task {
         id: next_id,
         description,
         completed: false,
+        priority: next_priority,
+        duration_secs: 0,
+        started_at: None,
+        due,
+        tags: tags.map(|t| parse_tags(&t)).unwrap_or_default(),
+        finished_at: None,
+        name,
     });
-    save_tasks(&tasks);
     println!("Task added.");
+    Ok(())
 }
 
-fn list_tasks() {
-    let tasks = load_tasks();
+/// Renders a task as its one-line list/search representation, including any
+/// due date and tags so both list_tasks and fuzzy_search stay in sync.
+fn format_task_line(task: &Task) -> String {
+    let status = if task.completed { "Completed" } else { "Pending" };
+    let mut line = format!("{}. {} - {}", task.id, task.description, status);
+    if let Some(due) = &task.due {
+        line.push_str(&format!(" (due {})", due));
+    }
+    if !task.tags.is_empty() {
+        line.push_str(&format!(" [{}]", task.tags.join(", ")));
+    }
+    line
+}
+
+fn is_overdue(task: &Task) -> bool {
+    match &task.due {
+        Some(due) => match DateTime::parse_from_rfc3339(due) {
+            Ok(due) => due < Utc::now(),
+            Err(_) => false,
+        },
+        None => false,
+    }
+}
+
+fn list_tasks(repo: &dyn Repository, archive: &dyn Repository, overdue_only: bool, finished_only: bool) {
+    let mut tasks = if finished_only {
+        archive.all_tasks()
+    } else {
+        repo.all_tasks()
+    };
+    tasks.sort_by_key(|t| t.priority);
+    if overdue_only {
+        tasks.retain(is_overdue);
+    }
     if tasks.is_empty() {
         println!("No tasks available.");
     } else {
         println!("Tasks:");
         for task in tasks {
-            println!(
-                "{}. {} - {}",
-                task.id,
-                task.description,
-                if task.completed {
-                    "Completed"
-                } else {
-                    "Pending"
-                }
-            );
+            println!("{}", format_task_line(&task));
         }
     }
 }
 
-fn remove_task(id: u32) -> Result<(), String> {
-    let mut tasks = load_tasks();
-    if let Some(index) = tasks.iter().position(|t| t.id == id) {
-        tasks.remove(index);
-        save_tasks(&tasks);
-        println!("Task removed.");
-        Ok(())
+fn reorder_task(repo: &mut dyn Repository, id: u32, reference_id: u32, before: bool) -> Result<(), String> {
+    let mut tasks = repo.all_tasks();
+    tasks.sort_by_key(|t| t.priority);
+
+    let target_index = match tasks.iter().position(|t| t.id == id) {
+        Some(index) => index,
+        None => return Err(format!("Task with id {} not found.", id)),
+    };
+    let task = tasks.remove(target_index);
+
+    let reference_index = match tasks.iter().position(|t| t.id == reference_id) {
+        Some(index) => index,
+        None => return Err(format!("Task with id {} not found.", reference_id)),
+    };
+
+    let insert_index = if before {
+        reference_index
     } else {
-        Err(format!("Task with id {} not found.", id))
+        reference_index + 1
+    };
+    tasks.insert(insert_index, task);
+
+    for (index, task) in tasks.iter_mut().enumerate() {
+        task.priority = index as u32 + 1;
+        repo.update_task(task);
     }
+
+    println!("Task {} reordered.", id);
+    Ok(())
 }
 
-fn toggle_task_completed(id: u32) -> Result<(), String> {
-    let mut tasks = load_tasks();
-    if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
-        task.completed = !task.completed;
-        let task_description = task.description.clone();
-        let task_status = if task.completed {
-            "Completed"
-        } else {
-            "Pending"
-        };
-        save_tasks(&tasks);
-        println!("Task '{}' is now {}.", task_description, task_status);
+fn remove_task(repo: &mut dyn Repository, id: Option<u32>, name: Option<String>) -> Result<(), String> {
+    let id = resolve_task_id(repo, id, name.as_deref())?;
+    if repo.remove_task(id) {
+        println!("Task removed.");
         Ok(())
     } else {
         Err(format!("Task with id {} not found.", id))
     }
 }
 
-fn edit_task(id: u32, new_description: String) -> Result<(), String> {
-    let mut tasks = load_tasks();
-    if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
-        task.description = new_description;
-        save_tasks(&tasks);
-        println!("Task with ID {} was updated.", id);
+/// Moves a completed task out of the active repository and into the archive,
+/// stamping `finished_at` so history is preserved without bloating the
+/// active list.
+fn archive_task(repo: &mut dyn Repository, archive: &mut dyn Repository, id: u32) {
+    if let Some(mut task) = repo.get_task(id) {
+        task.finished_at = Some(Utc::now().to_rfc3339());
+        archive.insert_task(task);
+        repo.remove_task(id);
+    }
+}
+
+fn toggle_task_completed(
+    repo: &mut dyn Repository,
+    archive: &mut dyn Repository,
+    id: Option<u32>,
+    name: Option<String>,
+) -> Result<(), String> {
+    let id = resolve_task_id(repo, id, name.as_deref())?;
+    if let Some(task) = repo.toggle(id) {
+        let status = if task.completed { "Completed" } else { "Pending" };
+        println!("Task '{}' is now {}.", task.description, status);
+        if task.completed {
+            archive_task(repo, archive, id);
+        }
         Ok(())
     } else {
         Err(format!("Task with id {} not found.", id))
     }
 }
 
-fn fuzzy_search(query: String) {
-    let tasks = load_tasks();
+fn start_task(repo: &mut dyn Repository, id: u32) -> Result<(), String> {
+    if repo.all_tasks().iter().any(|t| t.started_at.is_some()) {
+        return Err("Another task is already active.".to_string());
+    }
+    match repo.get_task(id) {
+        Some(mut task) => {
+            task.started_at = Some(Utc::now().to_rfc3339());
+            repo.update_task(&task);
+            println!("Task {} started.", id);
+            Ok(())
+        }
+        None => Err(format!("Task with id {} not found.", id)),
+    }
+}
+
+fn pause_active_task(repo: &mut dyn Repository) -> Result<(), String> {
+    match repo.all_tasks().into_iter().find(|t| t.started_at.is_some()) {
+        Some(mut task) => {
+            accumulate_elapsed(&mut task);
+            repo.update_task(&task);
+            println!("Task paused.");
+            Ok(())
+        }
+        None => Err("No task is currently active.".to_string()),
+    }
+}
+
+fn finish_active_task(repo: &mut dyn Repository, archive: &mut dyn Repository) -> Result<(), String> {
+    match repo.all_tasks().into_iter().find(|t| t.started_at.is_some()) {
+        Some(mut task) => {
+            accumulate_elapsed(&mut task);
+            task.completed = true;
+            let id = task.id;
+            repo.update_task(&task);
+            println!("Task {} finished.", id);
+            archive_task(repo, archive, id);
+            Ok(())
+        }
+        None => Err("No task is currently active.".to_string()),
+    }
+}
+
+/// Adds the time since `started_at` to `duration_secs` and clears `started_at`.
+fn accumulate_elapsed(task: &mut Task) {
+    if let Some(started_at) = task.started_at.take() {
+        let started_at: DateTime<Utc> = started_at
+            .parse()
+            .expect("started_at was not valid RFC3339");
+        let elapsed = (Utc::now() - started_at).num_seconds().max(0) as u64;
+        task.duration_secs += elapsed;
+    }
+}
+
+fn show_status(repo: &dyn Repository) {
+    match repo.all_tasks().into_iter().find(|t| t.started_at.is_some()) {
+        Some(task) => {
+            let started_at: DateTime<Utc> = task
+                .started_at
+                .as_ref()
+                .unwrap()
+                .parse()
+                .expect("started_at was not valid RFC3339");
+            let elapsed = task.duration_secs + (Utc::now() - started_at).num_seconds().max(0) as u64;
+            println!(
+                "Task {} ('{}') is active, {} seconds elapsed.",
+                task.id, task.description, elapsed
+            );
+        }
+        None => println!("No task is currently active."),
+    }
+}
+
+fn edit_task(
+    repo: &mut dyn Repository,
+    id: Option<u32>,
+    name: Option<String>,
+    new_description: String,
+    due: Option<String>,
+    tags: Option<String>,
+    rename: Option<String>,
+) -> Result<(), String> {
+    let id = resolve_task_id(repo, id, name.as_deref())?;
+    match repo.get_task(id) {
+        Some(mut task) => {
+            task.description = new_description;
+            if let Some(due) = due {
+                task.due = Some(parse_due(&due)?);
+            }
+            if let Some(tags) = tags {
+                task.tags = parse_tags(&tags);
+            }
+            if let Some(rename) = rename {
+                validate_name(&repo.all_tasks(), &rename, Some(id))?;
+                task.name = Some(rename);
+            }
+            repo.update_task(&task);
+            println!("Task with ID {} was updated.", id);
+            Ok(())
+        }
+        None => Err(format!("Task with id {} not found.", id)),
+    }
+}
+
+fn fuzzy_search(repo: &dyn Repository, query: String) {
+    let tasks = repo.all_tasks();
     let matcher = SkimMatcherV2::default();
     let mut found = false;
 
     for task in tasks {
         let task_str = format!(
-            "{} {} {}",
+            "{} {} {} {} {}",
             task.id,
             task.description,
             if task.completed {
                 "Completed"
             } else {
                 "Pending"
-            }
+            },
+            task.due.as_deref().unwrap_or(""),
+            task.tags.join(" "),
         );
         if matcher.fuzzy_match(&task_str, &query).is_some() {
-            println!(
-                "{}. {} - {}",
-                task.id,
-                task.description,
-                if task.completed {
-                    "Completed"
-                } else {
-                    "Pending"
-                }
-            );
+            println!("{}", format_task_line(&task));
             found = true;
         }
     }
@@ -192,21 +499,63 @@ fn fuzzy_search(query: String) {
 
 fn main() {
     let cli = Cli::parse();
+    let mut repo = build_repository(cli.backend.as_deref());
+    let mut archive = build_archive_repository(cli.backend.as_deref());
 
     let result = match &cli.command {
-        Commands::Add { description } => {
-            add_task(description.clone());
+        Commands::Add {
+            description,
+            due,
+            tags,
+            name,
+        } => add_task(
+            repo.as_mut(),
+            description.clone(),
+            due.clone(),
+            tags.clone(),
+            name.clone(),
+        ),
+        Commands::List { overdue, finished } => {
+            list_tasks(repo.as_ref(), archive.as_ref(), *overdue, *finished);
             Ok(())
         }
-        Commands::List => {
-            list_tasks();
-            Ok(())
+        Commands::Remove { id, name } => remove_task(repo.as_mut(), *id, name.clone()),
+        Commands::Toggle { id, name } => {
+            toggle_task_completed(repo.as_mut(), archive.as_mut(), *id, name.clone())
         }
-        Commands::Remove { id } => remove_task(*id),
-        Commands::Toggle { id } => toggle_task_completed(*id),
-        Commands::Edit { id, description } => edit_task(*id, description.clone()),
+        Commands::Edit {
+            id,
+            name,
+            description,
+            due,
+            tags,
+            rename,
+        } => edit_task(
+            repo.as_mut(),
+            *id,
+            name.clone(),
+            description.clone(),
+            due.clone(),
+            tags.clone(),
+            rename.clone(),
+        ),
         Commands::Search { query } => {
-            fuzzy_search(query.clone());
+            fuzzy_search(repo.as_ref(), query.clone());
+            Ok(())
+        }
+        Commands::Priority { id, position } => match position {
+            PriorityPosition::Before { id: reference_id } => {
+                reorder_task(repo.as_mut(), *id, *reference_id, true)
+            }
+            PriorityPosition::After { id: reference_id } => {
+                reorder_task(repo.as_mut(), *id, *reference_id, false)
+            }
+        },
+        Commands::Start { id } => start_task(repo.as_mut(), *id),
+        Commands::Pause => pause_active_task(repo.as_mut()),
+        Commands::Finish => finish_active_task(repo.as_mut(), archive.as_mut()),
+        Commands::Status => {
+            show_status(repo.as_ref());
             Ok(())
         }
     };
@@ -220,31 +569,40 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-    use std::path::Path;
 
-    fn reset_task_file() {
-        if Path::new(TASK_FILE).exists() {
-            fs::remove_file(TASK_FILE).expect("Failed to reset task file");
-        }
+    /// Builds a path under a fresh temp directory, so each test gets its own
+    /// backing file instead of racing other tests over a shared one.
+    fn test_file_path(file_name: &str) -> String {
+        let dir = tempfile::tempdir()
+            .expect("Could not create temp dir")
+            .keep();
+        dir.join(file_name).to_string_lossy().into_owned()
+    }
+
+    fn test_repo() -> JsonRepository {
+        JsonRepository::new(test_file_path("tasks.json"))
+    }
+
+    fn test_archive() -> JsonRepository {
+        JsonRepository::new(test_file_path("finished_tasks.json"))
     }
 
     #[test]
     fn test_add_task() {
-        reset_task_file();
-        add_task("Test task 1".to_string());
-        let tasks = load_tasks();
+        let mut repo = test_repo();
+        add_task(&mut repo, "Test task 1".to_string(), None, None, None).unwrap();
+        let tasks = repo.all_tasks();
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].description, "Test task 1");
-        assert_eq!(tasks[0].completed, false);
+        assert!(!tasks[0].completed);
     }
 
     #[test]
     fn test_add_multiple_tasks() {
-        reset_task_file();
-        add_task("Task 1".to_string());
-        add_task("Task 2".to_string());
-        let tasks = load_tasks();
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Task 2".to_string(), None, None, None).unwrap();
+        let tasks = repo.all_tasks();
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[0].description, "Task 1");
         assert_eq!(tasks[1].description, "Task 2");
@@ -254,49 +612,175 @@ mod tests {
 
     #[test]
     fn test_remove_task() {
-        reset_task_file();
-        add_task("Task 1".to_string());
-        add_task("Task 2".to_string());
-        remove_task(1).unwrap();
-        let tasks = load_tasks();
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Task 2".to_string(), None, None, None).unwrap();
+        remove_task(&mut repo, Some(1), None).unwrap();
+        let tasks = repo.all_tasks();
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].description, "Task 2");
     }
 
     #[test]
     fn test_remove_invalid_task() {
-        reset_task_file();
-        add_task("Task 1".to_string());
-        assert!(remove_task(999).is_err());
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        assert!(remove_task(&mut repo, Some(999), None).is_err());
     }
 
     #[test]
-    fn test_toggle_task_completed() {
-        reset_task_file();
-        add_task("Task 1".to_string());
-        toggle_task_completed(1).unwrap();
-        let tasks = load_tasks();
-        assert_eq!(tasks[0].completed, true);
-        toggle_task_completed(1).unwrap();
-        let tasks = load_tasks();
-        assert_eq!(tasks[0].completed, false);
+    fn test_toggle_task_completed_archives_task() {
+        let mut repo = test_repo();
+        let mut archive = test_archive();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        toggle_task_completed(&mut repo, &mut archive, Some(1), None).unwrap();
+        assert!(repo.all_tasks().is_empty());
+        let finished = archive.all_tasks();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].completed);
+        assert!(finished[0].finished_at.is_some());
+    }
+
+    #[test]
+    fn test_build_repository_and_archive_use_distinct_files_under_override() {
+        let dir = tempfile::tempdir().expect("Could not create temp dir").keep();
+        std::env::set_var("TASK_DATA_DIR", &dir);
+        let mut repo = build_repository(None);
+        let mut archive = build_archive_repository(None);
+        add_task(repo.as_mut(), "Task 1".to_string(), None, None, None).unwrap();
+        toggle_task_completed(repo.as_mut(), archive.as_mut(), Some(1), None).unwrap();
+        assert!(
+            repo.all_tasks().is_empty(),
+            "completed task should have been moved out of the active store"
+        );
+        assert_eq!(archive.all_tasks().len(), 1);
     }
 
     #[test]
     fn test_edit_task() {
-        reset_task_file();
-        add_task("Task 1".to_string());
-        edit_task(1, "Updated Task".to_string()).unwrap();
-        let tasks = load_tasks();
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        edit_task(
+            &mut repo,
+            Some(1),
+            None,
+            "Updated Task".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let tasks = repo.all_tasks();
         assert_eq!(tasks[0].description, "Updated Task");
     }
 
+    #[test]
+    fn test_resolve_task_by_name() {
+        let mut repo = test_repo();
+        add_task(
+            &mut repo,
+            "Task 1".to_string(),
+            None,
+            None,
+            Some("groceries".to_string()),
+        )
+        .unwrap();
+        edit_task(
+            &mut repo,
+            None,
+            Some("groceries".to_string()),
+            "Buy groceries".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let tasks = repo.all_tasks();
+        assert_eq!(tasks[0].description, "Buy groceries");
+    }
+
+    #[test]
+    fn test_add_task_rejects_whitespace_name() {
+        let mut repo = test_repo();
+        assert!(add_task(
+            &mut repo,
+            "Task 1".to_string(),
+            None,
+            None,
+            Some("bad name".to_string()),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_reorder_task_before() {
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Task 2".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Task 3".to_string(), None, None, None).unwrap();
+        reorder_task(&mut repo, 3, 1, true).unwrap();
+        let mut tasks = repo.all_tasks();
+        tasks.sort_by_key(|t| t.priority);
+        assert_eq!(
+            tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![3, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_start_refuses_second_active_task() {
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Task 2".to_string(), None, None, None).unwrap();
+        start_task(&mut repo, 1).unwrap();
+        assert!(start_task(&mut repo, 2).is_err());
+    }
+
+    #[test]
+    fn test_pause_accumulates_duration() {
+        let mut repo = test_repo();
+        add_task(&mut repo, "Task 1".to_string(), None, None, None).unwrap();
+        start_task(&mut repo, 1).unwrap();
+        pause_active_task(&mut repo).unwrap();
+        let tasks = repo.all_tasks();
+        assert!(tasks[0].started_at.is_none());
+    }
+
+    #[test]
+    fn test_add_task_with_due_and_tags() {
+        let mut repo = test_repo();
+        add_task(
+            &mut repo,
+            "Task 1".to_string(),
+            Some("2026-08-01T00:00:00Z".to_string()),
+            Some("work, urgent".to_string()),
+            None,
+        )
+        .unwrap();
+        let tasks = repo.all_tasks();
+        assert_eq!(tasks[0].due, Some("2026-08-01T00:00:00Z".to_string()));
+        assert_eq!(tasks[0].tags, vec!["work".to_string(), "urgent".to_string()]);
+    }
+
+    #[test]
+    fn test_add_task_rejects_invalid_due() {
+        let mut repo = test_repo();
+        assert!(add_task(
+            &mut repo,
+            "Task 1".to_string(),
+            Some("not-a-date".to_string()),
+            None,
+            None,
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_fuzzy_search() {
-        reset_task_file();
-        add_task("Write documentation".to_string());
-        add_task("Fix bug".to_string());
-        fuzzy_search("doc".to_string());
-        fuzzy_search("2".to_string());
+        let mut repo = test_repo();
+        add_task(&mut repo, "Write documentation".to_string(), None, None, None).unwrap();
+        add_task(&mut repo, "Fix bug".to_string(), None, None, None).unwrap();
+        fuzzy_search(&repo, "doc".to_string());
+        fuzzy_search(&repo, "2".to_string());
     }
 }
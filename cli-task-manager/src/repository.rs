@@ -0,0 +1,307 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Read;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Task {
+    pub id: u32,
+    pub description: String,
+    pub completed: bool,
+    pub priority: u32,
+    pub duration_secs: u64,
+    pub started_at: Option<String>,
+    pub due: Option<String>,
+    pub tags: Vec<String>,
+    pub finished_at: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Panics if more than one task is active at once; `start_task` is the only
+/// caller that should ever set `started_at`, and it already refuses to do so
+/// while another task is active, so tripping this means a backend bypassed it.
+fn assert_single_active(tasks: &[Task]) {
+    let active_count = tasks.iter().filter(|t| t.started_at.is_some()).count();
+    assert!(
+        active_count <= 1,
+        "invariant violated: {} tasks active at once",
+        active_count
+    );
+}
+
+/// Storage backend for tasks. Command functions take `impl Repository` so new
+/// storage engines can be added without touching command logic.
+pub trait Repository {
+    fn insert_task(&mut self, task: Task) -> Task;
+    fn update_task(&mut self, task: &Task) -> bool;
+    fn remove_task(&mut self, id: u32) -> bool;
+    fn all_tasks(&self) -> Vec<Task>;
+    fn get_task(&self, id: u32) -> Option<Task>;
+    fn toggle(&mut self, id: u32) -> Option<Task>;
+}
+
+/// Flat-file backend that preserves the manager's original behavior: the
+/// whole task list is read and rewritten on every operation.
+pub struct JsonRepository {
+    path: String,
+}
+
+impl JsonRepository {
+    pub fn new(path: impl Into<String>) -> Self {
+        JsonRepository { path: path.into() }
+    }
+
+    fn load(&self) -> Vec<Task> {
+        let file = File::open(&self.path);
+        let tasks = match file {
+            Ok(mut file) => {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)
+                    .expect("Could not read file");
+                serde_json::from_str(&contents).unwrap_or_else(|_| Vec::new())
+            }
+            Err(_) => Vec::new(), // Return empty if file doesn't exist
+        };
+        assert_single_active(&tasks);
+        tasks
+    }
+
+    fn save(&self, tasks: &Vec<Task>) {
+        assert_single_active(tasks);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.path)
+            .expect("Could not open file");
+        serde_json::to_writer_pretty(file, tasks).expect("Could not write to file");
+    }
+}
+
+impl Repository for JsonRepository {
+    fn insert_task(&mut self, task: Task) -> Task {
+        let mut tasks = self.load();
+        tasks.push(task.clone());
+        self.save(&tasks);
+        task
+    }
+
+    fn update_task(&mut self, task: &Task) -> bool {
+        let mut tasks = self.load();
+        if let Some(existing) = tasks.iter_mut().find(|t| t.id == task.id) {
+            *existing = task.clone();
+            self.save(&tasks);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_task(&mut self, id: u32) -> bool {
+        let mut tasks = self.load();
+        if let Some(index) = tasks.iter().position(|t| t.id == id) {
+            tasks.remove(index);
+            self.save(&tasks);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn all_tasks(&self) -> Vec<Task> {
+        self.load()
+    }
+
+    fn get_task(&self, id: u32) -> Option<Task> {
+        self.load().into_iter().find(|t| t.id == id)
+    }
+
+    fn toggle(&mut self, id: u32) -> Option<Task> {
+        let mut tasks = self.load();
+        let task = tasks.iter_mut().find(|t| t.id == id)?;
+        task.completed = !task.completed;
+        let updated = task.clone();
+        self.save(&tasks);
+        Some(updated)
+    }
+}
+
+/// SQLite-backed repository, created with a schema migration that builds the
+/// `tasks` table on first run.
+pub struct SqliteRepository {
+    conn: Connection,
+}
+
+impl SqliteRepository {
+    pub fn new(path: impl AsRef<str>) -> Self {
+        let conn = Connection::open(path.as_ref()).expect("Could not open sqlite database");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                completed INTEGER NOT NULL,
+                priority INTEGER NOT NULL,
+                duration_secs INTEGER NOT NULL,
+                started_at TEXT,
+                due TEXT,
+                tags TEXT NOT NULL,
+                finished_at TEXT,
+                name TEXT
+            )",
+            (),
+        )
+        .expect("Could not create tasks table");
+        SqliteRepository { conn }
+    }
+
+    fn row_to_task(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+        let tags: String = row.get(7)?;
+        Ok(Task {
+            id: row.get(0)?,
+            description: row.get(1)?,
+            completed: row.get::<_, i64>(2)? != 0,
+            priority: row.get(3)?,
+            duration_secs: row.get::<_, i64>(4)? as u64,
+            started_at: row.get(5)?,
+            due: row.get(6)?,
+            tags: if tags.is_empty() {
+                Vec::new()
+            } else {
+                tags.split(',').map(|t| t.to_string()).collect()
+            },
+            finished_at: row.get(8)?,
+            name: row.get(9)?,
+        })
+    }
+}
+
+impl Repository for SqliteRepository {
+    fn insert_task(&mut self, task: Task) -> Task {
+        self.conn
+            .execute(
+                "INSERT INTO tasks (id, description, completed, priority, duration_secs, started_at, due, tags, finished_at, name)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                (
+                    task.id,
+                    &task.description,
+                    task.completed as i64,
+                    task.priority,
+                    task.duration_secs as i64,
+                    &task.started_at,
+                    &task.due,
+                    task.tags.join(","),
+                    &task.finished_at,
+                    &task.name,
+                ),
+            )
+            .expect("Could not insert task");
+        task
+    }
+
+    fn update_task(&mut self, task: &Task) -> bool {
+        let rows = self
+            .conn
+            .execute(
+                "UPDATE tasks SET description = ?2, completed = ?3, priority = ?4,
+                 duration_secs = ?5, started_at = ?6, due = ?7, tags = ?8, finished_at = ?9, name = ?10 WHERE id = ?1",
+                (
+                    task.id,
+                    &task.description,
+                    task.completed as i64,
+                    task.priority,
+                    task.duration_secs as i64,
+                    &task.started_at,
+                    &task.due,
+                    task.tags.join(","),
+                    &task.finished_at,
+                    &task.name,
+                ),
+            )
+            .expect("Could not update task");
+        rows > 0
+    }
+
+    fn remove_task(&mut self, id: u32) -> bool {
+        let rows = self
+            .conn
+            .execute("DELETE FROM tasks WHERE id = ?1", (id,))
+            .expect("Could not remove task");
+        rows > 0
+    }
+
+    fn all_tasks(&self) -> Vec<Task> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, description, completed, priority, duration_secs, started_at, due, tags, finished_at, name FROM tasks")
+            .expect("Could not prepare query");
+        let tasks: Vec<Task> = stmt
+            .query_map((), Self::row_to_task)
+            .expect("Could not query tasks")
+            .map(|t| t.expect("Could not read task row"))
+            .collect();
+        assert_single_active(&tasks);
+        tasks
+    }
+
+    fn get_task(&self, id: u32) -> Option<Task> {
+        self.conn
+            .query_row(
+                "SELECT id, description, completed, priority, duration_secs, started_at, due, tags, finished_at, name FROM tasks WHERE id = ?1",
+                (id,),
+                Self::row_to_task,
+            )
+            .ok()
+    }
+
+    fn toggle(&mut self, id: u32) -> Option<Task> {
+        let mut task = self.get_task(id)?;
+        task.completed = !task.completed;
+        self.update_task(&task);
+        Some(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_task(id: u32) -> Task {
+        Task {
+            id,
+            description: "Task".to_string(),
+            completed: false,
+            priority: 1,
+            duration_secs: 0,
+            started_at: None,
+            due: None,
+            tags: vec!["work".to_string(), "urgent".to_string()],
+            finished_at: None,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_sqlite_repository_round_trips_insert_update_toggle_remove() {
+        let dir = tempfile::tempdir().expect("Could not create temp dir");
+        let path = dir.path().join("tasks.sqlite");
+        let mut repo = SqliteRepository::new(path.to_string_lossy());
+
+        repo.insert_task(test_task(1));
+        let tasks = repo.all_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].tags, vec!["work".to_string(), "urgent".to_string()]);
+
+        let mut updated = tasks[0].clone();
+        updated.description = "Updated".to_string();
+        assert!(repo.update_task(&updated));
+        assert_eq!(repo.get_task(1).unwrap().description, "Updated");
+
+        let toggled = repo.toggle(1).expect("Task with id 1 should exist");
+        assert!(toggled.completed);
+        assert!(repo.get_task(1).unwrap().completed);
+
+        assert!(repo.remove_task(1));
+        assert!(repo.get_task(1).is_none());
+        assert!(repo.all_tasks().is_empty());
+    }
+}